@@ -47,7 +47,8 @@ fn main() {
     println!("✅ Query validated");
 
     println!("\n🚀 Executing with apollo-compiler...\n");
-    let execution = Execution::new(&schema, &document);
+    let variables = serde_json::Map::new();
+    let execution = Execution::new(&schema, &document, &variables);
 
     match execution.execute_sync(&QueryResolver) {
         Ok(response) => {
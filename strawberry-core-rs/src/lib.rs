@@ -1,10 +1,15 @@
 /// Strawberry GraphQL execution engine using apollo-compiler
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyAny};
+use pyo3::types::{PyDict, PyList, PyAny};
 use apollo_compiler::{Schema, ExecutableDocument};
+use apollo_compiler::ast::Value as GraphQLValue;
+use apollo_compiler::schema::{ExtendedType, Type as GraphQLType};
 use apollo_compiler::resolvers::{Execution, ObjectValue, ResolvedValue, ResolveInfo, FieldError};
 use serde_json::Value as JsonValue;
-use std::sync::Arc;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, OnceLock};
 
 /// Convert camelCase to snake_case
 fn camel_to_snake(s: &str) -> String {
@@ -22,14 +27,163 @@ fn camel_to_snake(s: &str) -> String {
     result
 }
 
+/// The Python `serialize`/`parse_value` callables registered for a custom GraphQL scalar.
+struct ScalarHooks {
+    serialize: PyObject,
+    parse_value: PyObject,
+}
+
+/// Scalar type name -> hooks, shared across every query execution in the process.
+fn scalar_registry() -> &'static Mutex<HashMap<String, ScalarHooks>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, ScalarHooks>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Register Strawberry's own built-in scalars (DateTime, Date, UUID, Decimal) the first time
+/// any query runs, so they work out of the box. `register_scalar` can override them whether
+/// called before or after this point: a pre-existing registration is left alone here, and a
+/// later call always overwrites unconditionally.
+fn ensure_builtin_scalars_registered(py: Python) {
+    static INIT: OnceLock<()> = OnceLock::new();
+    INIT.get_or_init(|| {
+        if let Err(e) = register_builtin_scalars(py) {
+            e.print(py);
+        }
+    });
+}
+
+fn register_builtin_scalars(py: Python) -> PyResult<()> {
+    register_scalar_hooks(
+        py,
+        "DateTime",
+        "lambda value: value.isoformat()",
+        "lambda value: __import__('datetime').datetime.fromisoformat(value)",
+    )?;
+    register_scalar_hooks(
+        py,
+        "Date",
+        "lambda value: value.isoformat()",
+        "lambda value: __import__('datetime').date.fromisoformat(value)",
+    )?;
+    register_scalar_hooks(
+        py,
+        "UUID",
+        "lambda value: str(value)",
+        "lambda value: __import__('uuid').UUID(value)",
+    )?;
+    register_scalar_hooks(
+        py,
+        "Decimal",
+        "lambda value: str(value)",
+        "lambda value: __import__('decimal').Decimal(value)",
+    )?;
+    Ok(())
+}
+
+/// Install the built-in hooks for `name` unless a caller has already registered their own
+/// (e.g. via `register_scalar` at module-import time, before the first query runs) — the
+/// built-in bootstrap must never clobber a pre-existing override.
+fn register_scalar_hooks(py: Python, name: &str, serialize_src: &str, parse_value_src: &str) -> PyResult<()> {
+    if scalar_registry().lock().unwrap().contains_key(name) {
+        return Ok(());
+    }
+    let serialize = py.eval(serialize_src, None, None)?.into();
+    let parse_value = py.eval(parse_value_src, None, None)?.into();
+    scalar_registry().lock().unwrap().insert(name.to_string(), ScalarHooks { serialize, parse_value });
+    Ok(())
+}
+
+/// Register the output `serialize` and input `parse_value` callables for a custom GraphQL
+/// scalar named `name`, overriding any existing (including built-in) hooks for that name.
+#[pyfunction]
+fn register_scalar(name: &str, serialize: PyObject, parse_value: PyObject) -> PyResult<()> {
+    scalar_registry().lock().unwrap().insert(name.to_string(), ScalarHooks { serialize, parse_value });
+    Ok(())
+}
+
+/// If `value` is a coroutine (the result of calling an `async def` resolver), drive it to
+/// completion while the GIL is held, and return the awaited result. Non-coroutine values pass
+/// through unchanged. A nested async resolver's own coroutine is awaited the same way,
+/// recursively, during its `resolve_field` call.
+///
+/// When this "synchronous" extension is called from code that is itself running inside an
+/// event loop (e.g. from an `async def` handler), the current thread's loop is already
+/// running: `run_until_complete` on it raises `RuntimeError: this event loop is already
+/// running`, and blocking on that loop from the thread driving it would never return. That
+/// case runs the coroutine to completion on a fresh loop in a separate worker thread instead.
+fn await_if_coroutine<'a>(py: Python<'a>, value: &'a PyAny) -> PyResult<&'a PyAny> {
+    let asyncio = py.import("asyncio")?;
+    if !asyncio.getattr("iscoroutine")?.call1((value,))?.extract::<bool>()? {
+        return Ok(value);
+    }
+
+    let event_loop = match asyncio.getattr("get_event_loop")?.call0() {
+        Ok(event_loop) => event_loop,
+        Err(_) => {
+            let event_loop = asyncio.getattr("new_event_loop")?.call0()?;
+            asyncio.getattr("set_event_loop")?.call1((event_loop,))?;
+            event_loop
+        }
+    };
+
+    if event_loop.getattr("is_running")?.call0()?.extract::<bool>()? {
+        let executor = py.import("concurrent.futures")?.getattr("ThreadPoolExecutor")?.call1((1,))?;
+        let future = executor.call_method1("submit", (asyncio.getattr("run")?, value))?;
+        let result = future.call_method0("result");
+        executor.call_method1("shutdown", (false,))?;
+        return result;
+    }
+
+    event_loop.getattr("run_until_complete")?.call1((value,))
+}
+
+/// A single entry of a GraphQL response `errors` array: a message plus the response path
+/// (field names and list indices, root to leaf) of the field whose resolution failed.
+#[derive(Clone, serde::Serialize)]
+struct CollectedError {
+    message: String,
+    path: Vec<JsonValue>,
+}
+
+/// Accumulates [`CollectedError`]s across one query execution. Resolvers never surface a
+/// per-field failure as a fatal `Err` from `resolve_field` — instead they record it here and
+/// resolve the field to `null`, so one bad field nulls out just that position (and, per
+/// GraphQL null propagation, its nearest nullable ancestor if the field itself is non-null)
+/// instead of discarding the whole response.
+#[derive(Clone)]
+struct ErrorSink(Rc<RefCell<Vec<CollectedError>>>);
+
+impl ErrorSink {
+    fn new() -> Self {
+        Self(Rc::new(RefCell::new(Vec::new())))
+    }
+
+    fn record(&self, path: Vec<JsonValue>, message: impl Into<String>) {
+        self.0.borrow_mut().push(CollectedError { message: message.into(), path });
+    }
+
+    fn drain(&self) -> Vec<CollectedError> {
+        std::mem::take(&mut *self.0.borrow_mut())
+    }
+}
+
+/// Append a response-path segment (a field name or list index) to `path`.
+fn child_path(path: &[JsonValue], segment: JsonValue) -> Vec<JsonValue> {
+    let mut path = path.to_vec();
+    path.push(segment);
+    path
+}
+
 struct JsonResolver {
     type_name: String,
     data: JsonValue,
+    path: Vec<JsonValue>,
+    errors: ErrorSink,
 }
 
 impl JsonResolver {
-    fn new(type_name: String, data: JsonValue) -> Self {
-        Self { type_name, data }
+    fn new(type_name: String, data: JsonValue, path: Vec<JsonValue>, errors: ErrorSink) -> Self {
+        Self { type_name, data, path, errors }
     }
 }
 
@@ -40,6 +194,7 @@ impl ObjectValue for JsonResolver {
 
     fn resolve_field<'a>(&'a self, info: &'a ResolveInfo<'a>) -> Result<ResolvedValue<'a>, FieldError> {
         let field_name = info.field_name();
+        let field_path = child_path(&self.path, JsonValue::String(field_name.to_string()));
         let value = match &self.data {
             JsonValue::Object(map) => map.get(field_name),
             _ => None,
@@ -54,35 +209,255 @@ impl ObjectValue for JsonResolver {
                 Ok(ResolvedValue::leaf(value.unwrap().clone()))
             }
             Some(JsonValue::Object(obj)) => {
-                // Try to get the type name from __typename field, otherwise infer from schema
-                let type_name = obj.get("__typename")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-                    .unwrap_or_else(|| {
-                        // Infer from field definition
-                        let field_def = info.field_definition();
-                        field_def.ty.inner_named_type().to_string()
-                    });
-
-                Ok(ResolvedValue::object(JsonResolver::new(
-                    type_name,
-                    value.unwrap().clone(),
-                )))
+                let declared_type = info.field_definition().ty.inner_named_type().as_str();
+                let schema = info.schema();
+                let typename = obj.get("__typename").and_then(|v| v.as_str());
+
+                let type_name = if is_abstract_type(schema, declared_type) {
+                    // An abstract position has no concrete type to fall back on; __typename
+                    // is mandatory here, unlike for plain object fields below.
+                    match typename.filter(|t| abstract_type_has_member(schema, declared_type, t)) {
+                        Some(t) => Some(t.to_string()),
+                        None => {
+                            self.errors.record(field_path.clone(), format!(
+                                "Abstract type \"{}\" must resolve to an object type",
+                                declared_type
+                            ));
+                            None
+                        }
+                    }
+                } else {
+                    Some(typename.map(|s| s.to_string()).unwrap_or_else(|| declared_type.to_string()))
+                };
+
+                match type_name {
+                    Some(type_name) => Ok(ResolvedValue::object(JsonResolver::new(
+                        type_name,
+                        value.unwrap().clone(),
+                        field_path,
+                        self.errors.clone(),
+                    ))),
+                    None => Ok(ResolvedValue::null()),
+                }
+            }
+            // A key simply absent from the backing JSON is an ordinary unset-optional-field,
+            // not an error: the schema (already validated by apollo-compiler) is the source
+            // of truth for which fields exist, so this resolver just treats a missing key
+            // the same as an explicit `null` rather than spamming the `errors` array.
+            None => Ok(ResolvedValue::null()),
+        }
+    }
+}
+
+/// Whether `type_name` names an interface or union in `schema`, i.e. a position where
+/// `inner_named_type()` is an abstract type rather than the concrete object to resolve.
+fn is_abstract_type(schema: &Schema, type_name: &str) -> bool {
+    matches!(
+        schema.types.get(type_name),
+        Some(ExtendedType::Interface(_)) | Some(ExtendedType::Union(_))
+    )
+}
+
+/// Whether `candidate` is a legal concrete type for the interface/union named `abstract_name`.
+fn abstract_type_has_member(schema: &Schema, abstract_name: &str, candidate: &str) -> bool {
+    match schema.types.get(abstract_name) {
+        Some(ExtendedType::Union(union_def)) => union_def.members.contains(candidate),
+        Some(ExtendedType::Interface(_)) => match schema.types.get(candidate) {
+            Some(ExtendedType::Object(object_def)) => object_def.implements_interfaces.contains(abstract_name),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// The inner type of a GraphQL list type, if `ty` is (nullable or non-null) a list.
+fn list_item_type(ty: &GraphQLType) -> Option<&GraphQLType> {
+    match ty {
+        GraphQLType::List(inner) | GraphQLType::NonNullList(inner) => Some(inner.as_ref()),
+        _ => None,
+    }
+}
+
+/// Field name -> field type for the input object type named by `ty`, if any.
+fn input_object_fields<'s>(schema: &'s Schema, ty: &GraphQLType) -> HashMap<&'s str, &'s GraphQLType> {
+    match schema.types.get(ty.inner_named_type()) {
+        Some(ExtendedType::InputObject(def)) => def
+            .fields
+            .iter()
+            .map(|(name, field)| (name.as_str(), &field.ty))
+            .collect(),
+        _ => HashMap::new(),
+    }
+}
+
+/// Convert a bound variable's JSON value (from the operation's `variables` map) into a
+/// Python object. Variables are JSON, not GraphQL AST literals, so this is a separate,
+/// simpler conversion from [`coerce_argument_value`]'s literal-coercion path.
+fn json_value_to_python(py: Python, value: &JsonValue) -> PyObject {
+    match value {
+        JsonValue::Null => py.None(),
+        JsonValue::Bool(b) => b.into_py(py),
+        JsonValue::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into_py(py)
+            } else if let Some(f) = n.as_f64() {
+                f.into_py(py)
+            } else {
+                py.None()
+            }
+        }
+        JsonValue::String(s) => s.as_str().into_py(py),
+        JsonValue::Array(items) => {
+            let converted: Vec<PyObject> = items.iter().map(|item| json_value_to_python(py, item)).collect();
+            PyList::new(py, converted).into_py(py)
+        }
+        JsonValue::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, value) in map {
+                dict.set_item(camel_to_snake(key), json_value_to_python(py, value)).ok();
             }
-            None => Err(self.unknown_field_error(info)),
+            dict.into_py(py)
+        }
+    }
+}
+
+/// Coerce a single GraphQL argument/input value into the Python object a resolver expects.
+///
+/// Returns `Ok(None)` when the value is absent (no literal and no variable binding) so the
+/// caller can leave the corresponding Python kwarg out entirely and let the resolver's own
+/// default apply; an explicit GraphQL `null` coerces to `Ok(Some(py.None()))`.
+fn coerce_argument_value(
+    py: Python,
+    value: Option<&GraphQLValue>,
+    ty: &GraphQLType,
+    schema: &Schema,
+    info: &ResolveInfo,
+) -> PyResult<Option<PyObject>> {
+    // `variable_value` hands back the already-coerced operation variable as a `&JsonValue`
+    // (the document's variables were resolved into JSON up front by `resolve_variables`), not
+    // an AST `GraphQLValue` literal, so it goes through `json_value_to_python` here rather
+    // than the literal-matching arms below.
+    if let Some(GraphQLValue::Variable(name)) = value {
+        return match info.variable_value(name.as_str()) {
+            Some(bound) if bound.is_null() => Ok(Some(py.None())),
+            Some(bound) => apply_parse_value_hook(py, ty, json_value_to_python(py, bound)).map(Some),
+            None => Ok(None),
+        };
+    }
+
+    let value = match value {
+        Some(other) => other,
+        None => return Ok(None),
+    };
+
+    if matches!(value, GraphQLValue::Null) {
+        return Ok(Some(py.None()));
+    }
+
+    // Lists and input objects recurse per-element/per-field, each applying any custom scalar
+    // hook at its own leaf position, so only a genuine scalar/enum value is a candidate here.
+    let is_scalar_leaf = !matches!(value, GraphQLValue::List(_) | GraphQLValue::Object(_));
+
+    let coerced = match value {
+        GraphQLValue::String(s) => s.as_str().into_py(py),
+        GraphQLValue::Int(i) => match i.try_to_i32() {
+            Ok(n) => n.into_py(py),
+            // Still a number outside i32 range: widen to i64, and if it doesn't even fit
+            // that, fall back to Python's own arbitrary-precision int parser. Never hand the
+            // resolver a plain string for what the query declared as an Int.
+            Err(_) => match i.as_str().parse::<i64>() {
+                Ok(n) => n.into_py(py),
+                Err(_) => py.import("builtins")?.getattr("int")?.call1((i.as_str(),))?.into(),
+            },
+        },
+        GraphQLValue::Float(f) => f.get().into_py(py),
+        GraphQLValue::Boolean(b) => b.into_py(py),
+        GraphQLValue::Enum(name) => name.as_str().into_py(py),
+        GraphQLValue::List(items) => {
+            let item_ty = list_item_type(ty).unwrap_or(ty);
+            let mut converted = Vec::with_capacity(items.len());
+            for item in items {
+                let item_value = coerce_argument_value(py, Some(item), item_ty, schema, info)?
+                    .unwrap_or_else(|| py.None());
+                converted.push(item_value);
+            }
+            PyList::new(py, converted).into_py(py)
+        }
+        GraphQLValue::Object(fields) => {
+            let input_fields = input_object_fields(schema, ty);
+            let dict = PyDict::new(py);
+            for (field_name, field_value) in fields {
+                let field_ty = match input_fields.get(field_name.as_str()) {
+                    Some(field_ty) => *field_ty,
+                    None => continue,
+                };
+                let coerced = coerce_argument_value(py, Some(field_value), field_ty, schema, info)?
+                    .unwrap_or_else(|| py.None());
+                dict.set_item(camel_to_snake(field_name.as_str()), coerced)?;
+            }
+            dict.into_py(py)
+        }
+        GraphQLValue::Null | GraphQLValue::Variable(_) => unreachable!("handled above"),
+    };
+
+    if is_scalar_leaf {
+        return apply_parse_value_hook(py, ty, coerced).map(Some);
+    }
+
+    Ok(Some(coerced))
+}
+
+/// Run a registered custom scalar's `parse_value` hook over an already-coerced leaf value,
+/// whether it came from a literal or a variable binding; passes the value through unchanged
+/// if no hook is registered for `ty`'s named type.
+fn apply_parse_value_hook(py: Python, ty: &GraphQLType, value: PyObject) -> PyResult<PyObject> {
+    let parse_value_hook = scalar_registry()
+        .lock()
+        .unwrap()
+        .get(ty.inner_named_type().as_str())
+        .map(|hooks| hooks.parse_value.clone_ref(py));
+
+    match parse_value_hook {
+        Some(parse_value) => Ok(parse_value.as_ref(py).call1((value,))?.into()),
+        None => Ok(value),
+    }
+}
+
+/// Build the Python `kwargs` dict for the field currently being resolved, coercing each
+/// declared argument from the query's AST (or its schema default) into a Python value.
+fn extract_kwargs<'a>(py: Python<'a>, info: &ResolveInfo, schema: &Schema) -> PyResult<Option<&'a PyDict>> {
+    let field_def = info.field_definition();
+    if field_def.arguments.is_empty() {
+        return Ok(None);
+    }
+
+    let provided_args = &info.field().arguments;
+    let kwargs = PyDict::new(py);
+    for arg_def in &field_def.arguments {
+        let provided = provided_args.iter().find(|arg| arg.name == arg_def.name);
+        let raw_value = provided
+            .map(|arg| arg.value.as_ref())
+            .or_else(|| arg_def.default_value.as_deref());
+
+        if let Some(coerced) = coerce_argument_value(py, raw_value, &arg_def.ty, schema, info)? {
+            kwargs.set_item(camel_to_snake(arg_def.name.as_str()), coerced)?;
         }
     }
+
+    Ok(Some(kwargs))
 }
 
 /// Resolver that calls Python resolver functions
 struct PythonResolver {
     type_name: String,
     py_object: PyObject,  // The Python object (e.g., Query instance, Stadium instance)
+    path: Vec<JsonValue>,
+    errors: ErrorSink,
 }
 
 impl PythonResolver {
-    fn new(type_name: String, py_object: PyObject) -> Self {
-        Self { type_name, py_object }
+    fn new(type_name: String, py_object: PyObject, path: Vec<JsonValue>, errors: ErrorSink) -> Self {
+        Self { type_name, py_object, path, errors }
     }
 }
 
@@ -93,6 +468,7 @@ impl ObjectValue for PythonResolver {
 
     fn resolve_field<'a>(&'a self, info: &'a ResolveInfo<'a>) -> Result<ResolvedValue<'a>, FieldError> {
         let field_name = info.field_name();
+        let field_path = child_path(&self.path, JsonValue::String(field_name.to_string()));
 
         Python::with_gil(|py| {
             let obj = self.py_object.as_ref(py);
@@ -104,20 +480,49 @@ impl ObjectValue for PythonResolver {
                     // If that fails, try converting to snake_case
                     let snake_case = camel_to_snake(field_name);
                     obj.getattr(snake_case.as_str())
-                })
-                .map_err(|e| self.unknown_field_error(info))?;
+                });
+
+            let result = match result {
+                Ok(result) => result,
+                Err(e) => {
+                    self.errors.record(field_path, format!("Field \"{}\" was not found: {}", field_name, e));
+                    return Ok(ResolvedValue::null());
+                }
+            };
 
             // Check if it's a method (callable)
             if result.is_callable() {
-                // It's a method, we need to call it
-                // TODO: Extract arguments from GraphQL query
-                let call_result = result.call0()
-                    .map_err(|_e| self.unknown_field_error(info))?;
+                // It's a method, we need to call it with its coerced GraphQL arguments
+                let kwargs = match extract_kwargs(py, info, info.schema()) {
+                    Ok(kwargs) => kwargs,
+                    Err(e) => {
+                        self.errors.record(field_path, e.to_string());
+                        return Ok(ResolvedValue::null());
+                    }
+                };
 
-                python_to_resolved_value(py, call_result, info)
+                let call_result = match result.call((), kwargs) {
+                    Ok(call_result) => call_result,
+                    Err(e) => {
+                        self.errors.record(field_path, e.to_string());
+                        return Ok(ResolvedValue::null());
+                    }
+                };
+
+                // `async def` resolvers return a coroutine rather than the actual value;
+                // drive it to completion here so the rest of the pipeline never sees one.
+                let call_result = match await_if_coroutine(py, call_result) {
+                    Ok(call_result) => call_result,
+                    Err(e) => {
+                        self.errors.record(field_path, e.to_string());
+                        return Ok(ResolvedValue::null());
+                    }
+                };
+
+                python_to_resolved_value(py, call_result, info, &field_path, &self.errors)
             } else {
                 // It's a property/attribute, use it directly
-                python_to_resolved_value(py, result, info)
+                python_to_resolved_value(py, result, info, &field_path, &self.errors)
             }
         })
     }
@@ -126,34 +531,74 @@ impl ObjectValue for PythonResolver {
 /// Try to serialize a Python object to JSON for fast processing
 fn try_serialize_to_json(py: Python, value: &PyAny) -> Option<JsonValue> {
     // Try dataclasses.asdict first (for Strawberry types)
-    if let Ok(dataclasses) = py.import("dataclasses") {
-        if let Ok(is_dataclass) = dataclasses.getattr("is_dataclass") {
-            if let Ok(result) = is_dataclass.call1((value,)) {
-                if let Ok(true) = result.extract::<bool>() {
-                    // It's a dataclass! Convert to dict
-                    if let Ok(asdict) = dataclasses.getattr("asdict") {
-                        if let Ok(dict_result) = asdict.call1((value,)) {
-                            // Serialize to JSON
-                            if let Ok(json_module) = py.import("json") {
-                                if let Ok(dumps) = json_module.getattr("dumps") {
-                                    if let Ok(json_str) = dumps.call1((dict_result,)) {
-                                        if let Ok(json_str) = json_str.extract::<String>() {
-                                            // Parse in Rust
-                                            if let Ok(json_val) = serde_json::from_str::<JsonValue>(&json_str) {
-                                                return Some(json_val);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    let dataclasses = py.import("dataclasses").ok()?;
+    let is_dataclass = dataclasses.getattr("is_dataclass").ok()?.call1((value,)).ok()?;
+    if !is_dataclass.extract::<bool>().ok()? {
+        return None;
+    }
+
+    // It's a dataclass! Convert to dict, then straight to JSON without a dumps/loads round trip.
+    let dict_result = dataclasses.getattr("asdict").ok()?.call1((value,)).ok()?;
+    python_to_json(py, dict_result).ok()
+}
+
+/// Convert a Python value directly into a `serde_json::Value`, without going through the
+/// `json` module's `dumps`/`loads` — skips a UTF-8 string allocation and a second parse pass,
+/// and keeps any integer in the `i64`/`u64` range exact instead of rounding it through a float.
+/// A larger integer is converted to a float.
+fn python_to_json(py: Python, value: &PyAny) -> PyResult<JsonValue> {
+    if value.is_none() {
+        return Ok(JsonValue::Null);
+    }
+
+    // bool must be checked before int: in Python, bool is a subclass of int.
+    if let Ok(b) = value.extract::<bool>() {
+        return Ok(JsonValue::Bool(b));
+    }
+
+    if let Ok(i) = value.extract::<i64>() {
+        return Ok(JsonValue::Number(i.into()));
+    }
+
+    if let Ok(i) = value.extract::<u64>() {
+        return Ok(JsonValue::Number(i.into()));
+    }
+
+    if let Ok(f) = value.extract::<f64>() {
+        return serde_json::Number::from_f64(f)
+            .map(JsonValue::Number)
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Cannot represent non-finite float {} as JSON", f
+            )));
+    }
+
+    if let Ok(s) = value.extract::<String>() {
+        return Ok(JsonValue::String(s));
+    }
+
+    if let Ok(list) = value.downcast::<pyo3::types::PyList>() {
+        return list.iter().map(|item| python_to_json(py, item)).collect::<PyResult<_>>().map(JsonValue::Array);
+    }
+
+    if let Ok(tuple) = value.downcast::<pyo3::types::PyTuple>() {
+        return tuple.iter().map(|item| python_to_json(py, item)).collect::<PyResult<_>>().map(JsonValue::Array);
+    }
+
+    if let Ok(dict) = value.downcast::<PyDict>() {
+        let mut map = serde_json::Map::with_capacity(dict.len());
+        for (key, val) in dict.iter() {
+            let key = match key.extract::<String>() {
+                Ok(key) => key,
+                Err(_) => key.str()?.extract::<String>()?,
+            };
+            map.insert(key, python_to_json(py, val)?);
         }
+        return Ok(JsonValue::Object(map));
     }
 
-    None
+    Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+        "Cannot convert Python value of type {} to JSON", value.get_type().name()?
+    )))
 }
 
 /// Convert a Python value to a ResolvedValue
@@ -161,12 +606,37 @@ fn python_to_resolved_value<'a>(
     py: Python,
     value: &PyAny,
     info: &'a ResolveInfo<'a>,
+    path: &[JsonValue],
+    errors: &ErrorSink,
 ) -> Result<ResolvedValue<'a>, FieldError> {
     // Check for None
     if value.is_none() {
         return Ok(ResolvedValue::null());
     }
 
+    // A registered custom scalar (DateTime, UUID, Decimal, or a user's own) takes priority
+    // over the generic scalar/object handling below, since e.g. a `datetime` would otherwise
+    // fail every extract() and fall through to being treated as an object resolver.
+    let declared_type = info.field_definition().ty.inner_named_type().as_str().to_string();
+    let serialize_hook = scalar_registry().lock().unwrap().get(&declared_type).map(|hooks| hooks.serialize.clone_ref(py));
+    if let Some(serialize) = serialize_hook {
+        let serialized = match serialize.as_ref(py).call1((value,)) {
+            Ok(serialized) => serialized,
+            Err(e) => {
+                errors.record(path.to_vec(), e.to_string());
+                return Ok(ResolvedValue::null());
+            }
+        };
+        let json = match python_to_json(py, serialized) {
+            Ok(json) => json,
+            Err(e) => {
+                errors.record(path.to_vec(), e.to_string());
+                return Ok(ResolvedValue::null());
+            }
+        };
+        return Ok(ResolvedValue::leaf(json));
+    }
+
     // Try to extract scalar types
     if let Ok(s) = value.extract::<String>() {
         return Ok(ResolvedValue::leaf(s));
@@ -193,9 +663,10 @@ fn python_to_resolved_value<'a>(
         // Convert list items to ResolvedValues one by one
         let mut resolved_items = Vec::new();
 
-        for item in list.iter() {
+        for (index, item) in list.iter().enumerate() {
             // Recursively convert each item
-            let resolved = python_to_resolved_value(py, item, info)?;
+            let item_path = child_path(path, JsonValue::from(index));
+            let resolved = python_to_resolved_value(py, item, info, &item_path, errors)?;
             resolved_items.push(resolved);
         }
 
@@ -206,32 +677,130 @@ fn python_to_resolved_value<'a>(
     // DON'T serialize top-level objects to JSON - apollo-compiler needs resolvers for them
     // Only lists get the JSON optimization
 
-    // Create a PythonResolver for this object
-    let type_name = {
-        // Try to get __class__.__name__ from the Python object
-        if let Ok(class) = value.getattr("__class__") {
-            if let Ok(name) = class.getattr("__name__") {
-                if let Ok(name_str) = name.extract::<String>() {
-                    name_str
-                } else {
-                    // Fall back to schema inference
-                    info.field_definition().ty.inner_named_type().to_string()
-                }
-            } else {
-                info.field_definition().ty.inner_named_type().to_string()
+    let declared_type = info.field_definition().ty.inner_named_type().as_str();
+    let schema = info.schema();
+    let class_name = python_typename_hint(value);
+
+    let type_name = if is_abstract_type(schema, declared_type) {
+        // An abstract position has no concrete fallback: we need an explicit __typename/
+        // _strawberry_type attribute or class name, and it must be a real member.
+        match class_name.filter(|c| abstract_type_has_member(schema, declared_type, c)) {
+            Some(candidate) => candidate,
+            None => {
+                errors.record(path.to_vec(), format!(
+                    "Cannot resolve abstract type \"{}\": value has no matching concrete type",
+                    declared_type
+                ));
+                return Ok(ResolvedValue::null());
             }
-        } else {
-            info.field_definition().ty.inner_named_type().to_string()
         }
+    } else {
+        class_name.unwrap_or_else(|| declared_type.to_string())
     };
 
     // Create a new PythonResolver for this object
     let py_object = value.into();
-    Ok(ResolvedValue::object(PythonResolver::new(type_name, py_object)))
+    Ok(ResolvedValue::object(PythonResolver::new(type_name, py_object, path.to_vec(), errors.clone())))
+}
+
+/// Best-effort concrete type name for a Python resolver value: an explicit `__typename` or
+/// `_strawberry_type` attribute wins (the value may be an instance of a class shared across
+/// GraphQL object types), otherwise fall back to the Python class name. Only attributes are
+/// read: a `"__typename"` key of a plain dict is not consulted.
+fn python_typename_hint(value: &PyAny) -> Option<String> {
+    for attr in ["__typename", "_strawberry_type"] {
+        if let Ok(attr_value) = value.getattr(attr) {
+            if let Ok(name) = attr_value.extract::<String>() {
+                return Some(name);
+            }
+        }
+    }
+    value.getattr("__class__").ok()?.getattr("__name__").ok()?.extract::<String>().ok()
+}
+
+/// Convert an optional `PyDict` of operation variables to a `serde_json::Map`, then check
+/// it against the document's variable definitions: a required variable (non-null type, no
+/// default) that is missing is an error, as is a provided variable whose JSON shape doesn't
+/// match its declared scalar type.
+fn resolve_variables(py: Python, variables: Option<&PyDict>, document: &ExecutableDocument) -> PyResult<serde_json::Map<String, JsonValue>> {
+    let variables = match variables {
+        Some(variables) => match python_to_json(py, variables)? {
+            JsonValue::Object(map) => map,
+            _ => serde_json::Map::new(),
+        },
+        None => serde_json::Map::new(),
+    };
+
+    for operation in document.operations.iter() {
+        for var_def in &operation.variables {
+            let name = var_def.name.as_str();
+            match variables.get(name) {
+                Some(value) if value.is_null() && var_def.ty.is_non_null() => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Variable \"${}\" of non-null type \"{}\" must not be null",
+                        name, var_def.ty
+                    )));
+                }
+                Some(value) => {
+                    if !json_matches_scalar_type(value, var_def.ty.inner_named_type().as_str()) {
+                        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                            "Variable \"${}\" got invalid value for type \"{}\"",
+                            name, var_def.ty
+                        )));
+                    }
+                }
+                None if var_def.ty.is_non_null() && var_def.default_value.is_none() => {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Missing required variable \"${}\"",
+                        name
+                    )));
+                }
+                None => {}
+            }
+        }
+    }
+
+    Ok(variables)
+}
+
+/// Whether a JSON value is shaped like the named built-in GraphQL scalar. Custom scalars,
+/// lists and input objects are accepted as-is since their real validation happens during
+/// argument coercion.
+fn json_matches_scalar_type(value: &JsonValue, type_name: &str) -> bool {
+    if value.is_null() || value.is_array() {
+        return true;
+    }
+    match type_name {
+        "String" => value.is_string(),
+        // Per spec, ID serializes as a string but accepts either a String or an Int literal.
+        "ID" => value.is_string() || value.is_i64() || value.is_u64(),
+        "Int" => value.is_i64() || value.is_u64(),
+        "Float" => value.is_number(),
+        "Boolean" => value.is_boolean(),
+        _ => true,
+    }
+}
+
+/// Build the spec-shaped `{"data": ..., "errors": [...]}` response object. `errors` is
+/// omitted when empty, matching the GraphQL-over-HTTP convention that a fully successful
+/// response has no `errors` key at all.
+fn build_response_envelope(response: &impl serde::Serialize, errors: Vec<CollectedError>) -> PyResult<JsonValue> {
+    let mut envelope = serde_json::Map::new();
+    envelope.insert(
+        "data".to_string(),
+        serde_json::to_value(response).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?,
+    );
+    if !errors.is_empty() {
+        envelope.insert(
+            "errors".to_string(),
+            serde_json::to_value(errors).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?,
+        );
+    }
+    Ok(JsonValue::Object(envelope))
 }
 
 #[pyfunction]
-fn execute_query(schema_sdl: &str, query: &str, root_data: &PyDict) -> PyResult<String> {
+fn execute_query(schema_sdl: &str, query: &str, root_data: &PyDict, variables: Option<&PyDict>) -> PyResult<String> {
     let schema = Schema::parse(schema_sdl, "schema.graphql")
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:?}", e)))?
         .validate()
@@ -242,21 +811,19 @@ fn execute_query(schema_sdl: &str, query: &str, root_data: &PyDict) -> PyResult<
         .validate(&schema)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:?}", e)))?;
 
-    let json_str = Python::with_gil(|py| {
-        let json_module = py.import("json")?;
-        let dumps = json_module.getattr("dumps")?;
-        dumps.call1((root_data,))?.extract::<String>()
-    })?;
+    Python::with_gil(|py| ensure_builtin_scalars_registered(py));
+
+    let root_value = Python::with_gil(|py| python_to_json(py, root_data))?;
 
-    let root_value: JsonValue = serde_json::from_str(&json_str)
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    let variables = Python::with_gil(|py| resolve_variables(py, variables, &document))?;
 
-    let resolver = JsonResolver::new("Query".to_string(), root_value);
-    let execution = Execution::new(&schema, &document);
+    let errors = ErrorSink::new();
+    let resolver = JsonResolver::new("Query".to_string(), root_value, Vec::new(), errors.clone());
+    let execution = Execution::new(&schema, &document, &variables);
     let response = execution.execute_sync(&resolver)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{:?}", e)))?;
 
-    serde_json::to_string_pretty(&response)
+    serde_json::to_string_pretty(&build_response_envelope(&response, errors.drain())?)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
 }
 
@@ -266,6 +833,7 @@ fn execute_query_with_resolvers(
     schema_sdl: &str,
     query: &str,
     root_value: PyObject,  // The root Query instance
+    variables: Option<&PyDict>,
 ) -> PyResult<String> {
     let schema = Schema::parse(schema_sdl, "schema.graphql")
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:?}", e)))?
@@ -277,12 +845,17 @@ fn execute_query_with_resolvers(
         .validate(&schema)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{:?}", e)))?;
 
-    let resolver = PythonResolver::new("Query".to_string(), root_value);
-    let execution = Execution::new(&schema, &document);
+    Python::with_gil(|py| ensure_builtin_scalars_registered(py));
+
+    let variables = Python::with_gil(|py| resolve_variables(py, variables, &document))?;
+
+    let errors = ErrorSink::new();
+    let resolver = PythonResolver::new("Query".to_string(), root_value, Vec::new(), errors.clone());
+    let execution = Execution::new(&schema, &document, &variables);
     let response = execution.execute_sync(&resolver)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{:?}", e)))?;
 
-    serde_json::to_string_pretty(&response)
+    serde_json::to_string_pretty(&build_response_envelope(&response, errors.drain())?)
         .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
 }
 
@@ -290,5 +863,6 @@ fn execute_query_with_resolvers(
 fn strawberry_core_rs(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(execute_query, m)?)?;
     m.add_function(wrap_pyfunction!(execute_query_with_resolvers, m)?)?;
+    m.add_function(wrap_pyfunction!(register_scalar, m)?)?;
     Ok(())
 }